@@ -2,7 +2,7 @@ use exonum::blockchain::{self, Block};
 use exonum::crypto::Hash;
 use exonum::helpers::Height;
 use exonum::messages::Precommit;
-use exonum::storage::{Snapshot, ProofMapIndex, MapProof, StorageValue};
+use exonum::storage::{Snapshot, ProofMapIndex, ProofListIndex, MapProof, ListProof, StorageValue};
 use exonum::storage::proof_map_index::ProofMapKey;
 
 use serde::{Serialize, Serializer};
@@ -57,6 +57,27 @@ where
     }
 }
 
+/// A single entry of a `ProofListIndex`, proven by position against the
+/// list's current root (which still validly attests to past entries even
+/// after later ones are appended).
+#[derive(Serialize)]
+pub struct ListView<V: Serialize> {
+    proof: ListProof<V>,
+    entries: Vec<V>,
+}
+
+impl<V> ListView<V>
+where
+    V: StorageValue + Serialize,
+{
+    pub fn new<T: AsRef<Snapshot>>(table: &ProofListIndex<T, V>, index: u64) -> Self {
+        ListView {
+            proof: table.get_range_proof(index, index + 1),
+            entries: table.get(index).into_iter().collect(),
+        }
+    }
+}
+
 pub struct BlockWithState<T: Serialize> {
     block: Block,
     precommits: Vec<Precommit>,
@@ -64,19 +85,30 @@ pub struct BlockWithState<T: Serialize> {
 }
 
 impl<T: Serialize> BlockWithState<T> {
+    /// Build a proof of `table_view` together with the block and precommits
+    /// at `anchor_height`. `table_view`'s own `MapProof`/`ListProof` is
+    /// always checked against the table's *current* root (storage keeps no
+    /// historical tree snapshots) regardless of `anchor_height`, so callers
+    /// cannot use `anchor_height` to obtain a block/precommit proof for
+    /// anything other than the current chain tip; it exists only to name the
+    /// height the returned block/precommit proof actually anchors. A caller
+    /// proving something about an earlier point in time (e.g. a wallet's
+    /// balance at a past height) must rely on history recorded inside the
+    /// entity itself (e.g. a wallet's `history_hash`, which is only updated
+    /// going forward), not on `anchor_height`.
     pub fn new<S: AsRef<Snapshot>>(
         snapshot: S,
         service_id: u16,
         table_id: usize,
         table_view: T,
+        anchor_height: Height,
     ) -> Self {
         let table_key = StateTableKey {
             service_id,
             table_id,
         };
         let schema = blockchain::Schema::new(&snapshot);
-        let max_height = schema.block_hashes_by_height().len() - 1;
-        let block_proof = schema.block_and_precommits(Height(max_height)).unwrap();
+        let block_proof = schema.block_and_precommits(anchor_height).unwrap();
 
         let proof_to_table = schema.get_proof_to_service_table(service_id, table_id);
 
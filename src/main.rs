@@ -30,15 +30,16 @@ use exonum::blockchain::{self, Blockchain, Service, GenesisConfig, ValidatorKeys
                          ApiContext};
 use exonum::node::{Node, NodeConfig, NodeApiConfig, TransactionSend, ApiSender, NodeChannel};
 use exonum::messages::{RawTransaction, FromRaw, Message};
-use exonum::storage::{Snapshot, Fork, MemoryDB, ProofMapIndex};
-use exonum::crypto::{PublicKey, Hash, HexValue};
+use exonum::storage::{Snapshot, Fork, MemoryDB, ProofMapIndex, ProofListIndex};
+use exonum::crypto::{self, PublicKey, Hash, HexValue, Signature, SIGNATURE_LENGTH};
+use exonum::helpers::Height;
 use exonum::encoding;
 use exonum::api::{Api, ApiError};
 use iron::prelude::*;
 use iron::Handler;
 use router::Router;
 
-use self::api::{BlockWithState, MapView};
+use self::api::{BlockWithState, MapView, ListView};
 
 mod api;
 
@@ -54,10 +55,41 @@ const TX_CREATE_WALLET_ID: u16 = 1;
 
 const TX_TRANSFER_ID: u16 = 2;
 
+const TX_FAUCET_WITHDRAW_ID: u16 = 3;
+
+const TX_LOCK_ID: u16 = 4;
+
+const TX_CLAIM_ID: u16 = 5;
+
+const TX_REFUND_ID: u16 = 6;
+
+const TX_REGISTER_ASSET_ID: u16 = 7;
+
+const TX_TRANSFER_ASSET_ID: u16 = 8;
+
+/// Identifies a registered asset type. The native coin tracked by `Wallet`
+/// is not itself an `AssetId` entry; this identifies the additional,
+/// separately-denominated tokens a wallet can hold.
+type AssetId = u16;
+
 // Define initial balance of a newly created wallet.
 
 const INIT_BALANCE: u64 = 100;
 
+// Number of base balance units that make up one whole token. `balance` is a
+// bare `u64` of base units, but faucet limits are easier to reason about (and
+// configure) in whole tokens, so amounts crossing that boundary are scaled by
+// this factor.
+const DENOMINATION: u64 = 100;
+
+// Maximum number of whole tokens a single wallet may withdraw from the
+// faucet within a `WINDOW_BLOCKS`-sized rolling window.
+const FAUCET_LIMIT: u64 = 10;
+
+// Height of the window after which a wallet's faucet withdrawal counter
+// resets.
+const WINDOW_BLOCKS: u64 = 1440;
+
 // // // // // // // // // // PERSISTENT DATA // // // // // // // // // //
 
 // Declare the data to be stored in the blockchain. In the present case,
@@ -68,25 +100,139 @@ const INIT_BALANCE: u64 = 100;
 /// struct and determine bounds of its fields with `encoding_struct!` macro.
 encoding_struct! {
     struct Wallet {
-        const SIZE = 48;
+        const SIZE = 88;
 
         field pub_key:            &PublicKey  [00 => 32]
         field name:               &str        [32 => 40]
         field balance:            u64         [40 => 48]
+        field history_len:        u64         [48 => 56]
+        field history_hash:       &Hash       [56 => 88]
     }
 }
 
-/// Add methods to the `Wallet` type for changing balance.
+/// Add methods to the `Wallet` type for changing balance. These only update
+/// `balance`; `history_len`/`history_hash` are kept in sync separately by
+/// `CurrencySchema::record_balance`, which also has access to the wallet's
+/// history list.
 impl Wallet {
     pub fn increase(self, amount: u64) -> Self {
         let balance = self.balance() + amount;
-        Self::new(self.pub_key(), self.name(), balance)
+        Self::new(
+            self.pub_key(),
+            self.name(),
+            balance,
+            self.history_len(),
+            self.history_hash(),
+        )
     }
 
     pub fn decrease(self, amount: u64) -> Self {
         let balance = self.balance() - amount;
-        Self::new(self.pub_key(), self.name(), balance)
+        Self::new(
+            self.pub_key(),
+            self.name(),
+            balance,
+            self.history_len(),
+            self.history_hash(),
+        )
+    }
+}
+
+/// Tracks how much a wallet has pulled from the faucet within the current
+/// withdrawal window, so that the per-wallet limit can be enforced across
+/// several `TxFaucetWithdraw` transactions.
+encoding_struct! {
+    struct FaucetRecord {
+        const SIZE = 16;
+
+        field withdrawn:    u64  [00 => 08]
+        field last_reset:   u64  [08 => 16]
+    }
+}
+
+/// An amount escrowed by `TxLock` pending a matching `TxClaim` (before
+/// `timeout`) or a `TxRefund` (at or after `timeout`).
+encoding_struct! {
+    struct LockedSwap {
+        const SIZE = 112;
+
+        field from:        &PublicKey  [00 => 32]
+        field to:          &PublicKey  [32 => 64]
+        field amount:      u64         [64 => 72]
+        field hashlock:    &Hash       [72 => 104]
+        field timeout:     u64         [104 => 112]
+    }
+}
+
+/// Metadata for a registered asset type. `denomination` records how many
+/// base units (as stored in the balances index) equal one display unit, so
+/// the REST layer can present and parse human-readable amounts.
+encoding_struct! {
+    struct AssetMeta {
+        const SIZE = 18;
+
+        field asset_id:      u16   [00 => 02]
+        field name:          &str  [02 => 10]
+        field denomination:  u64   [10 => 18]
+    }
+}
+
+/// One entry in a wallet's balance history: the balance it held from
+/// `height` onward (until the next recorded entry, if any).
+encoding_struct! {
+    struct BalanceSnapshot {
+        const SIZE = 16;
+
+        field height:   u64  [00 => 08]
+        field balance:  u64  [08 => 16]
+    }
+}
+
+/// `ProofMapIndex` only has built-in key support for `PublicKey` and `Hash`,
+/// so asset registry entries and per-asset balances are keyed by a digest of
+/// their logical key instead of adding a new `ProofMapKey` impl.
+fn asset_key(asset_id: AssetId) -> Hash {
+    crypto::hash(&asset_id.to_le_bytes())
+}
+
+fn asset_balance_key(pub_key: &PublicKey, asset_id: AssetId) -> Hash {
+    let mut bytes = Vec::with_capacity(34);
+    bytes.extend_from_slice(pub_key.as_ref());
+    bytes.extend_from_slice(&asset_id.to_le_bytes());
+    crypto::hash(&bytes)
+}
+
+/// The height of the most recently committed block, i.e. the height to pass
+/// to `BlockWithState::new` for a proof of the current state.
+fn latest_height<S: AsRef<Snapshot>>(snapshot: &S) -> Height {
+    let schema = blockchain::Schema::new(snapshot);
+    Height(schema.block_hashes_by_height().len() - 1)
+}
+
+/// Encodes `bytes` as a lowercase hex string. Used for the arbitrary-length
+/// signable transaction payload in the detached-signing endpoints; fixed-size
+/// crypto types (`PublicKey`, `Hash`, `Signature`) go through `HexValue`
+/// instead.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The inverse of `to_hex`.
+fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    if !s.is_ascii() {
+        return Err("Non-ASCII hex string".into());
+    }
+    let bytes = s.as_bytes();
+    if bytes.len() % 2 != 0 {
+        return Err("Odd-length hex string".into());
     }
+    (0..bytes.len())
+        .step_by(2)
+        .map(|i| {
+            let pair = std::str::from_utf8(&bytes[i..i + 2]).unwrap();
+            u8::from_str_radix(pair, 16).map_err(|e| e.to_string())
+        })
+        .collect()
 }
 
 // // // // // // // // // // DATA LAYOUT // // // // // // // // // //
@@ -112,8 +258,88 @@ impl<T: AsRef<Snapshot>> CurrencySchema<T> {
         return self.wallets().get(key);
     }
 
+    pub fn faucet_records(&self) -> ProofMapIndex<&Snapshot, PublicKey, FaucetRecord> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 1, &());
+        let view: &Snapshot = self.view.as_ref();
+        ProofMapIndex::new(prefix, view)
+    }
+
+    pub fn faucet_record(&self, key: &PublicKey) -> Option<FaucetRecord> {
+        self.faucet_records().get(key)
+    }
+
+    pub fn nonces(&self) -> ProofMapIndex<&Snapshot, PublicKey, u64> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 2, &());
+        let view: &Snapshot = self.view.as_ref();
+        ProofMapIndex::new(prefix, view)
+    }
+
+    /// The nonce a sender's next `TxTransfer` is expected to carry.
+    pub fn nonce(&self, key: &PublicKey) -> u64 {
+        self.nonces().get(key).unwrap_or(0)
+    }
+
+    pub fn swaps(&self) -> ProofMapIndex<&Snapshot, Hash, LockedSwap> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 3, &());
+        let view: &Snapshot = self.view.as_ref();
+        ProofMapIndex::new(prefix, view)
+    }
+
+    pub fn swap(&self, swap_id: &Hash) -> Option<LockedSwap> {
+        self.swaps().get(swap_id)
+    }
+
+    /// Preimages revealed by a `TxClaim`, kept around after the matching
+    /// `LockedSwap` is removed so the counterparty can observe them on-chain.
+    pub fn revealed_preimages(&self) -> ProofMapIndex<&Snapshot, Hash, Vec<u8>> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 4, &());
+        let view: &Snapshot = self.view.as_ref();
+        ProofMapIndex::new(prefix, view)
+    }
+
+    pub fn assets(&self) -> ProofMapIndex<&Snapshot, Hash, AssetMeta> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 5, &());
+        let view: &Snapshot = self.view.as_ref();
+        ProofMapIndex::new(prefix, view)
+    }
+
+    pub fn asset(&self, asset_id: AssetId) -> Option<AssetMeta> {
+        self.assets().get(&asset_key(asset_id))
+    }
+
+    pub fn asset_balances(&self) -> ProofMapIndex<&Snapshot, Hash, u64> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 6, &());
+        let view: &Snapshot = self.view.as_ref();
+        ProofMapIndex::new(prefix, view)
+    }
+
+    pub fn asset_balance(&self, pub_key: &PublicKey, asset_id: AssetId) -> u64 {
+        self.asset_balances()
+            .get(&asset_balance_key(pub_key, asset_id))
+            .unwrap_or(0)
+    }
+
+    /// A wallet's full balance history, one entry per `record_balance` call.
+    /// Scoped by `pub_key` via `gen_prefix`, so its root is not part of
+    /// `state_hash` directly; instead the wallet's own `history_hash` field
+    /// records this list's root as of its last update, so a `ListProof` of
+    /// any entry can be checked against the wallet as currently committed.
+    pub fn balance_history(&self, pub_key: &PublicKey) -> ProofListIndex<&Snapshot, BalanceSnapshot> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 7, pub_key);
+        let view: &Snapshot = self.view.as_ref();
+        ProofListIndex::new(prefix, view)
+    }
+
     pub fn state_hash(&self) -> Vec<Hash> {
-        return vec![self.wallets().root_hash()];
+        return vec![
+            self.wallets().root_hash(),
+            self.faucet_records().root_hash(),
+            self.nonces().root_hash(),
+            self.swaps().root_hash(),
+            self.revealed_preimages().root_hash(),
+            self.assets().root_hash(),
+            self.asset_balances().root_hash(),
+        ];
     }
 }
 
@@ -132,6 +358,57 @@ impl<'a> CurrencySchema<&'a mut Fork> {
         let prefix = blockchain::gen_prefix(SERVICE_ID, 0, &());
         ProofMapIndex::new(prefix, self.view)
     }
+
+    pub fn faucet_records_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, FaucetRecord> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 1, &());
+        ProofMapIndex::new(prefix, self.view)
+    }
+
+    pub fn nonces_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 2, &());
+        ProofMapIndex::new(prefix, self.view)
+    }
+
+    pub fn swaps_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, LockedSwap> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 3, &());
+        ProofMapIndex::new(prefix, self.view)
+    }
+
+    pub fn revealed_preimages_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Vec<u8>> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 4, &());
+        ProofMapIndex::new(prefix, self.view)
+    }
+
+    pub fn assets_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, AssetMeta> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 5, &());
+        ProofMapIndex::new(prefix, self.view)
+    }
+
+    pub fn balance_history_mut(&mut self, pub_key: &PublicKey) -> ProofListIndex<&mut Fork, BalanceSnapshot> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 7, pub_key);
+        ProofListIndex::new(prefix, self.view)
+    }
+
+    /// Append `wallet`'s current balance to its history list at `height`,
+    /// returning an updated `Wallet` whose `history_len`/`history_hash`
+    /// reflect the append. Callers should store the returned wallet instead
+    /// of the one passed in.
+    pub fn record_balance(&mut self, wallet: Wallet, height: u64) -> Wallet {
+        let pub_key = *wallet.pub_key();
+        let balance = wallet.balance();
+
+        let mut history = self.balance_history_mut(&pub_key);
+        history.push(BalanceSnapshot::new(height, balance));
+        let history_len = history.len();
+        let history_hash = history.root_hash();
+
+        Wallet::new(wallet.pub_key(), wallet.name(), balance, history_len, &history_hash)
+    }
+
+    pub fn asset_balances_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, u64> {
+        let prefix = blockchain::gen_prefix(SERVICE_ID, 6, &());
+        ProofMapIndex::new(prefix, self.view)
+    }
 }
 
 // // // // // // // // // // TRANSACTIONS // // // // // // // // // //
@@ -148,7 +425,9 @@ message! {
     }
 }
 
-/// Transfer coins between the wallets.
+/// Transfer coins between the wallets. `nonce` must match the sender's
+/// expected next nonce (see `CurrencySchema::nonce`), which both orders a
+/// sender's transfers and rejects replays of an already-applied transaction.
 message! {
     struct TxTransfer {
         const TYPE = SERVICE_ID;
@@ -158,7 +437,120 @@ message! {
         field from:        &PublicKey  [00 => 32]
         field to:          &PublicKey  [32 => 64]
         field amount:      u64         [64 => 72]
-        field seed:        u64         [72 => 80]
+        field nonce:       u64         [72 => 80]
+    }
+}
+
+/// Withdraw coins from the system-controlled faucet reserve into a wallet,
+/// subject to a rolling per-wallet limit. `amount` is denominated in whole
+/// tokens, matching `FAUCET_LIMIT`.
+message! {
+    struct TxFaucetWithdraw {
+        const TYPE = SERVICE_ID;
+        const ID = TX_FAUCET_WITHDRAW_ID;
+        const SIZE = 40;
+
+        field pub_key:     &PublicKey  [00 => 32]
+        field amount:      u64         [32 => 40]
+    }
+}
+
+/// Escrow `amount` from `from`, redeemable by whoever first presents a
+/// `TxClaim` with the preimage of `hashlock`, or refundable to the sender via
+/// `TxRefund` once the chain reaches `timeout`. There is no client-chosen
+/// `swap_id` field: it is derived (see `derive_swap_id`) from this swap's own
+/// `from`/`to`/`hashlock`/`timeout`, so only the holder of `from`'s secret
+/// key can ever produce a signed `TxLock` landing on a given swap id, and a
+/// third party cannot squat it ahead of the intended sender.
+message! {
+    struct TxLock {
+        const TYPE = SERVICE_ID;
+        const ID = TX_LOCK_ID;
+        const SIZE = 112;
+
+        field from:        &PublicKey  [00 => 32]
+        field to:          &PublicKey  [32 => 64]
+        field amount:      u64         [64 => 72]
+        field hashlock:    &Hash       [72 => 104]
+        field timeout:     u64         [104 => 112]
+    }
+}
+
+/// The id a `TxLock` escrows its swap under, and the id a matching `TxClaim`
+/// or `TxRefund` must reference. Deriving it from the swap's own fields
+/// (rather than accepting it as a client-chosen value) means nobody but the
+/// holder of `from`'s secret key can produce a signed `TxLock` that lands on
+/// a particular swap id, closing off front-running squats of a pending lock.
+fn derive_swap_id(from: &PublicKey, to: &PublicKey, hashlock: &Hash, timeout: u64) -> Hash {
+    let mut bytes = Vec::with_capacity(32 + 32 + 32 + 8);
+    bytes.extend_from_slice(from.as_ref());
+    bytes.extend_from_slice(to.as_ref());
+    bytes.extend_from_slice(hashlock.as_ref());
+    bytes.extend_from_slice(&timeout.to_le_bytes());
+    crypto::hash(&bytes)
+}
+
+/// Redeem a `LockedSwap` by revealing `preimage`. Anyone may submit this
+/// transaction; the escrowed amount always goes to the swap's recorded
+/// recipient regardless of who signs it. `swap_id` must be the matching
+/// `TxLock`'s `derive_swap_id(from, to, hashlock, timeout)`, which the
+/// claimant recomputes locally from the swap's known terms.
+message! {
+    struct TxClaim {
+        const TYPE = SERVICE_ID;
+        const ID = TX_CLAIM_ID;
+        const SIZE = 72;
+
+        field pub_key:     &PublicKey  [00 => 32]
+        field swap_id:     &Hash       [32 => 64]
+        field preimage:    &[u8]       [64 => 72]
+    }
+}
+
+/// Return a timed-out `LockedSwap`'s escrowed amount to its original sender.
+/// `swap_id` must be the matching `TxLock`'s
+/// `derive_swap_id(from, to, hashlock, timeout)`.
+message! {
+    struct TxRefund {
+        const TYPE = SERVICE_ID;
+        const ID = TX_REFUND_ID;
+        const SIZE = 64;
+
+        field pub_key:     &PublicKey  [00 => 32]
+        field swap_id:     &Hash       [32 => 64]
+    }
+}
+
+/// Register a new asset type under `asset_id`, crediting the registrant with
+/// `supply` units so the asset has a non-zero circulating balance for
+/// `TxTransferAsset` to move between wallets.
+message! {
+    struct TxRegisterAsset {
+        const TYPE = SERVICE_ID;
+        const ID = TX_REGISTER_ASSET_ID;
+        const SIZE = 58;
+
+        field pub_key:       &PublicKey  [00 => 32]
+        field asset_id:      u16         [32 => 34]
+        field denomination:  u64         [34 => 42]
+        field supply:        u64         [42 => 50]
+        field name:          &str        [50 => 58]
+    }
+}
+
+/// Transfer units of a registered asset between two wallets. Shares the
+/// sender's nonce sequence with `TxTransfer`.
+message! {
+    struct TxTransferAsset {
+        const TYPE = SERVICE_ID;
+        const ID = TX_TRANSFER_ASSET_ID;
+        const SIZE = 82;
+
+        field from:        &PublicKey  [00 => 32]
+        field to:          &PublicKey  [32 => 64]
+        field asset_id:    u16         [64 => 66]
+        field amount:      u64         [66 => 74]
+        field nonce:       u64         [74 => 82]
     }
 }
 
@@ -174,9 +566,11 @@ impl Transaction for TxCreateWallet {
 
     /// Apply logic to the storage when executing the transaction.
     fn execute(&self, view: &mut Fork) {
+        let height = blockchain::Schema::new(&*view).height();
         let mut schema = CurrencySchema::rw(view);
         if schema.wallet(self.pub_key()).is_none() {
-            let wallet = Wallet::new(self.pub_key(), self.name(), INIT_BALANCE);
+            let wallet = Wallet::new(self.pub_key(), self.name(), INIT_BALANCE, 0, &Hash::default());
+            let wallet = schema.record_balance(wallet, height.0);
             println!("Create the wallet: {:?}", wallet);
             schema.wallets_mut().put(self.pub_key(), wallet)
         }
@@ -191,10 +585,15 @@ impl Transaction for TxTransfer {
     }
 
     /// Retrieve two wallets to apply the transfer. Check the sender's
-    /// balance and apply changes to the balances of the wallets.
+    /// nonce and balance, and apply changes to the balances of the wallets.
     fn execute(&self, view: &mut Fork) {
+        let height = blockchain::Schema::new(&*view).height();
         let mut schema = CurrencySchema::rw(view);
 
+        if self.nonce() != schema.nonce(self.from()) {
+            return;
+        }
+
         let sender = schema.wallet(self.from());
         let receiver = schema.wallet(self.to());
 
@@ -204,15 +603,273 @@ impl Transaction for TxTransfer {
             if sender.balance() >= amount {
                 let sender = sender.decrease(amount);
                 let receiver = receiver.increase(amount);
+                let sender = schema.record_balance(sender, height.0);
+                let receiver = schema.record_balance(receiver, height.0);
                 println!("Transfer between wallets: {:?} => {:?}", sender, receiver);
                 let mut wallets = schema.wallets_mut();
                 wallets.put(self.from(), sender);
                 wallets.put(self.to(), receiver);
+                schema.nonces_mut().put(self.from(), self.nonce() + 1);
             }
         }
     }
 }
 
+impl Transaction for TxFaucetWithdraw {
+    /// Check the requester's signature; the faucet trusts whoever controls
+    /// the recipient key to withdraw on its own behalf.
+    fn verify(&self) -> bool {
+        self.verify_signature(self.pub_key())
+    }
+
+    /// Reset the wallet's withdrawal counter once `WINDOW_BLOCKS` have
+    /// passed since it was last reset, then credit the wallet as long as
+    /// doing so would not push its withdrawals within the window past
+    /// `FAUCET_LIMIT`. Unfunded wallets are created on first withdrawal.
+    fn execute(&self, view: &mut Fork) {
+        let height = blockchain::Schema::new(&*view).height();
+        let mut schema = CurrencySchema::rw(view);
+
+        let wallet = schema
+            .wallet(self.pub_key())
+            .unwrap_or_else(|| Wallet::new(self.pub_key(), "", 0, 0, &Hash::default()));
+
+        let record = schema
+            .faucet_record(self.pub_key())
+            .unwrap_or_else(|| FaucetRecord::new(0, height.0));
+
+        let (withdrawn, last_reset) = if height.0 - record.last_reset() >= WINDOW_BLOCKS {
+            (0, height.0)
+        } else {
+            (record.withdrawn(), record.last_reset())
+        };
+
+        let requested = match self.amount().checked_mul(DENOMINATION) {
+            Some(requested) => requested,
+            None => return,
+        };
+        if withdrawn + requested > FAUCET_LIMIT * DENOMINATION {
+            return;
+        }
+
+        let wallet = wallet.increase(requested);
+        let wallet = schema.record_balance(wallet, height.0);
+        let record = FaucetRecord::new(withdrawn + requested, last_reset);
+        println!("Faucet withdrawal: {:?}", wallet);
+        schema.wallets_mut().put(self.pub_key(), wallet);
+        schema.faucet_records_mut().put(self.pub_key(), record);
+    }
+}
+
+impl Transaction for TxLock {
+    /// Check that the sender is not the receiver. Check correctness of the
+    /// sender's signature.
+    fn verify(&self) -> bool {
+        (*self.from() != *self.to()) && self.verify_signature(self.from())
+    }
+
+    /// Debit the sender and escrow the amount under this swap's derived id
+    /// (see `derive_swap_id`), refusing to overwrite an existing swap with
+    /// the same id.
+    fn execute(&self, view: &mut Fork) {
+        let height = blockchain::Schema::new(&*view).height();
+        let mut schema = CurrencySchema::rw(view);
+
+        let swap_id = derive_swap_id(self.from(), self.to(), self.hashlock(), self.timeout());
+        if schema.swap(&swap_id).is_some() {
+            return;
+        }
+
+        let sender = match schema.wallet(self.from()) {
+            Some(wallet) => wallet,
+            None => return,
+        };
+
+        let amount = self.amount();
+        if sender.balance() < amount {
+            return;
+        }
+
+        let sender = sender.decrease(amount);
+        let sender = schema.record_balance(sender, height.0);
+        let swap = LockedSwap::new(self.from(), self.to(), amount, self.hashlock(), self.timeout());
+        println!("Lock swap {:?}: {:?}", swap_id, swap);
+        schema.wallets_mut().put(self.from(), sender);
+        schema.swaps_mut().put(&swap_id, swap);
+    }
+}
+
+impl Transaction for TxClaim {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.pub_key())
+    }
+
+    /// Credit the swap's recipient once `preimage` hashes to the stored
+    /// `hashlock` and the timeout has not yet passed, then record the
+    /// preimage and remove the swap. A missing swap is a no-op, which keeps
+    /// claim and refund mutually exclusive and idempotent.
+    fn execute(&self, view: &mut Fork) {
+        let height = blockchain::Schema::new(&*view).height();
+        let mut schema = CurrencySchema::rw(view);
+
+        let swap = match schema.swap(self.swap_id()) {
+            Some(swap) => swap,
+            None => return,
+        };
+
+        if height.0 >= swap.timeout() || crypto::hash(self.preimage()) != *swap.hashlock() {
+            return;
+        }
+
+        let receiver = match schema.wallet(swap.to()) {
+            Some(wallet) => wallet,
+            None => return,
+        };
+
+        let receiver = receiver.increase(swap.amount());
+        let receiver = schema.record_balance(receiver, height.0);
+        println!("Claim swap {:?}: {:?}", self.swap_id(), receiver);
+        schema.wallets_mut().put(swap.to(), receiver);
+        schema
+            .revealed_preimages_mut()
+            .put(self.swap_id(), self.preimage().to_vec());
+        schema.swaps_mut().remove(self.swap_id());
+    }
+}
+
+impl Transaction for TxRefund {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.pub_key())
+    }
+
+    /// Return the escrowed amount to the swap's original sender once the
+    /// timeout has passed, then remove the swap. A missing swap is a no-op,
+    /// which keeps claim and refund mutually exclusive and idempotent.
+    fn execute(&self, view: &mut Fork) {
+        let height = blockchain::Schema::new(&*view).height();
+        let mut schema = CurrencySchema::rw(view);
+
+        let swap = match schema.swap(self.swap_id()) {
+            Some(swap) => swap,
+            None => return,
+        };
+
+        if height.0 < swap.timeout() {
+            return;
+        }
+
+        let sender = match schema.wallet(swap.from()) {
+            Some(wallet) => wallet,
+            None => return,
+        };
+
+        let sender = sender.increase(swap.amount());
+        let sender = schema.record_balance(sender, height.0);
+        println!("Refund swap {:?}: {:?}", self.swap_id(), sender);
+        schema.wallets_mut().put(swap.from(), sender);
+        schema.swaps_mut().remove(self.swap_id());
+    }
+}
+
+impl Transaction for TxRegisterAsset {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.pub_key())
+    }
+
+    /// Register `asset_id` with its display metadata and credit the
+    /// registrant with the initial `supply`, refusing to overwrite an asset
+    /// that is already registered.
+    fn execute(&self, view: &mut Fork) {
+        let mut schema = CurrencySchema::rw(view);
+
+        if schema.asset(self.asset_id()).is_some() {
+            return;
+        }
+
+        let meta = AssetMeta::new(self.asset_id(), self.name(), self.denomination());
+        println!("Register asset {}: {:?}", self.asset_id(), meta);
+        schema.assets_mut().put(&asset_key(self.asset_id()), meta);
+        schema.asset_balances_mut().put(
+            &asset_balance_key(self.pub_key(), self.asset_id()),
+            self.supply(),
+        );
+    }
+}
+
+impl Transaction for TxTransferAsset {
+    /// Check that the sender is not the receiver. Check correctness of the
+    /// sender's signature.
+    fn verify(&self) -> bool {
+        (*self.from() != *self.to()) && self.verify_signature(self.from())
+    }
+
+    /// Reject transfers of an unregistered asset or of more than the
+    /// sender's balance, then move the asset between both wallets' balances.
+    fn execute(&self, view: &mut Fork) {
+        let mut schema = CurrencySchema::rw(view);
+
+        if self.nonce() != schema.nonce(self.from()) {
+            return;
+        }
+
+        if schema.asset(self.asset_id()).is_none() {
+            return;
+        }
+
+        if schema.wallet(self.from()).is_none() || schema.wallet(self.to()).is_none() {
+            return;
+        }
+
+        let amount = self.amount();
+        let sender_balance = schema.asset_balance(self.from(), self.asset_id());
+        if sender_balance < amount {
+            return;
+        }
+        let receiver_balance = schema.asset_balance(self.to(), self.asset_id());
+
+        println!(
+            "Transfer {} of asset {}: {:?} => {:?}",
+            amount,
+            self.asset_id(),
+            self.from(),
+            self.to()
+        );
+        schema.asset_balances_mut().put(
+            &asset_balance_key(self.from(), self.asset_id()),
+            sender_balance - amount,
+        );
+        schema.asset_balances_mut().put(
+            &asset_balance_key(self.to(), self.asset_id()),
+            receiver_balance + amount,
+        );
+        schema.nonces_mut().put(self.from(), self.nonce() + 1);
+    }
+}
+
+/// Decode `raw` into the concrete transaction type its `message_type`
+/// identifies. Shared by `Service::tx_from_raw` (transactions arriving from
+/// peers) and the `/v1/transactions/submit` endpoint (transactions assembled
+/// from a detached signature), so both paths agree on exactly which types
+/// this service accepts.
+fn transaction_from_raw(raw: RawTransaction) -> Result<Box<Transaction>, encoding::Error> {
+    let trans: Box<Transaction> = match raw.message_type() {
+        TX_TRANSFER_ID => Box::new(TxTransfer::from_raw(raw)?),
+        TX_CREATE_WALLET_ID => Box::new(TxCreateWallet::from_raw(raw)?),
+        TX_FAUCET_WITHDRAW_ID => Box::new(TxFaucetWithdraw::from_raw(raw)?),
+        TX_LOCK_ID => Box::new(TxLock::from_raw(raw)?),
+        TX_CLAIM_ID => Box::new(TxClaim::from_raw(raw)?),
+        TX_REFUND_ID => Box::new(TxRefund::from_raw(raw)?),
+        TX_REGISTER_ASSET_ID => Box::new(TxRegisterAsset::from_raw(raw)?),
+        TX_TRANSFER_ASSET_ID => Box::new(TxTransferAsset::from_raw(raw)?),
+        _ => {
+            return Err(encoding::Error::IncorrectMessageType {
+                message_type: raw.message_type(),
+            });
+        }
+    };
+    Ok(trans)
+}
+
 // // // // // // // // // // REST API // // // // // // // // // //
 
 /// Implement the node API.
@@ -229,6 +886,44 @@ impl CryptocurrencyApi {
         let schema = CurrencySchema::ro(&snapshot);
         MapView::new(&schema.wallets(), pub_key)
     }
+
+    fn get_swap_view(&self, swap_id: Hash) -> MapView<Hash, LockedSwap> {
+        let snapshot = self.blockchain.snapshot();
+        let schema = CurrencySchema::ro(&snapshot);
+        MapView::new(&schema.swaps(), swap_id)
+    }
+
+    fn get_preimage_view(&self, swap_id: Hash) -> MapView<Hash, Vec<u8>> {
+        let snapshot = self.blockchain.snapshot();
+        let schema = CurrencySchema::ro(&snapshot);
+        MapView::new(&schema.revealed_preimages(), swap_id)
+    }
+
+    fn get_asset_view(&self, asset_id: AssetId) -> MapView<Hash, AssetMeta> {
+        let snapshot = self.blockchain.snapshot();
+        let schema = CurrencySchema::ro(&snapshot);
+        MapView::new(&schema.assets(), asset_key(asset_id))
+    }
+}
+
+/// A single registered asset's balance for a wallet, with the `MapProof`
+/// needed to verify it against the `asset_balances` root in `state_hash`.
+#[derive(Serialize)]
+struct AssetBalanceView {
+    asset_id: AssetId,
+    balance: MapView<Hash, u64>,
+}
+
+/// Chains a proof of one `balance_history` entry to the wallet it belongs
+/// to: `wallet`'s `MapProof` ties the wallet's current `history_hash` to
+/// `state_hash` (via the enclosing `BlockWithState`), and a verifier then
+/// checks that `history`'s `ListProof` computes to that same `history_hash`
+/// before trusting the entry it contains. Neither proof alone establishes
+/// that the historical balance belongs to this wallet's on-chain state.
+#[derive(Serialize)]
+struct WalletHistoryProof {
+    wallet: MapView<PublicKey, Wallet>,
+    history: ListView<BalanceSnapshot>,
 }
 
 /// The structure returned by the REST API.
@@ -237,6 +932,43 @@ struct TxInfo {
     tx_hash: Hash,
 }
 
+/// Returned by `/v1/transactions/build`: the exact byte range `verify_signature`
+/// checks, for an external signer to sign, together with its hash.
+#[derive(Serialize)]
+struct SignablePayload {
+    bytes: String,
+    hash: Hash,
+}
+
+/// Posted to `/v1/transactions/submit`: the `bytes` returned by
+/// `/v1/transactions/build`, unchanged, plus the Ed25519 signature produced
+/// over them by the detached signer.
+#[derive(Deserialize)]
+struct SignedSubmission {
+    bytes: String,
+    signature: String,
+}
+
+/// Tries to parse `body` as an unsigned `T` and, on success, returns the
+/// byte range `verify_signature` checks together with its hash. `body` must
+/// carry a placeholder `signature` field (any 64-byte hex value, e.g. all
+/// zeroes) so it deserializes like a normal `T`; the placeholder is stripped
+/// off before hashing and is never itself transmitted anywhere.
+fn try_build_transaction<T>(body: &serde_json::Value) -> Option<SignablePayload>
+where
+    T: Message,
+    for<'a> T: serde::Deserialize<'a>,
+{
+    serde_json::from_value::<T>(body.clone()).ok().map(|tx| {
+        let raw = tx.raw().as_ref();
+        let signable = &raw[..raw.len() - SIGNATURE_LENGTH];
+        SignablePayload {
+            bytes: to_hex(signable),
+            hash: crypto::hash(signable),
+        }
+    })
+}
+
 impl CryptocurrencyApi {
     fn process_transaction<T>(&self, req: &mut Request) -> IronResult<Response>
     where
@@ -255,6 +987,43 @@ impl CryptocurrencyApi {
             Err(e) => Err(ApiError::IncorrectRequest(Box::new(e)))?,
         }
     }
+
+    /// Build the signable byte range for whichever of this service's
+    /// transaction types `body` matches (tried in the same order as the
+    /// `wire` routes), so a detached signer never needs this service's
+    /// field layout ahead of time.
+    fn build_transaction(&self, body: &serde_json::Value) -> Option<SignablePayload> {
+        try_build_transaction::<TxCreateWallet>(body)
+            .or_else(|| try_build_transaction::<TxTransfer>(body))
+            .or_else(|| try_build_transaction::<TxFaucetWithdraw>(body))
+            .or_else(|| try_build_transaction::<TxLock>(body))
+            .or_else(|| try_build_transaction::<TxClaim>(body))
+            .or_else(|| try_build_transaction::<TxRefund>(body))
+            .or_else(|| try_build_transaction::<TxRegisterAsset>(body))
+            .or_else(|| try_build_transaction::<TxTransferAsset>(body))
+    }
+
+    /// Reattach `submission`'s signature to its signable bytes, verify the
+    /// resulting transaction and enqueue it the same way `process_transaction`
+    /// enqueues an already-signed request body (which it does not verify).
+    fn submit_transaction(&self, submission: SignedSubmission) -> IronResult<Response> {
+        let mut raw_bytes =
+            from_hex(&submission.bytes).map_err(|_| ApiError::IncorrectRequest("Invalid hex in bytes".into()))?;
+        let signature = Signature::from_hex(&submission.signature).map_err(ApiError::FromHex)?;
+        raw_bytes.extend_from_slice(signature.as_ref());
+
+        let raw = RawTransaction::from_vec(raw_bytes);
+        let transaction = transaction_from_raw(raw).map_err(|e| ApiError::IncorrectRequest(Box::new(e)))?;
+
+        if !transaction.verify() {
+            Err(ApiError::IncorrectRequest("Invalid signature".into()))?;
+        }
+
+        let tx_hash = transaction.hash();
+        self.channel.send(transaction).map_err(ApiError::Events)?;
+        let json = TxInfo { tx_hash };
+        self.ok_response(&serde_json::to_value(&json).unwrap())
+    }
 }
 
 /// Implement the `Api` trait.
@@ -273,6 +1042,66 @@ impl Api for CryptocurrencyApi {
             self_.process_transaction::<TxTransfer>(req)
         };
 
+        let self_ = self.clone();
+        let tx_faucet = move |req: &mut Request| -> IronResult<Response> {
+            self_.process_transaction::<TxFaucetWithdraw>(req)
+        };
+
+        let self_ = self.clone();
+        let tx_lock = move |req: &mut Request| -> IronResult<Response> {
+            self_.process_transaction::<TxLock>(req)
+        };
+
+        let self_ = self.clone();
+        let tx_claim = move |req: &mut Request| -> IronResult<Response> {
+            self_.process_transaction::<TxClaim>(req)
+        };
+
+        let self_ = self.clone();
+        let tx_refund = move |req: &mut Request| -> IronResult<Response> {
+            self_.process_transaction::<TxRefund>(req)
+        };
+
+        let self_ = self.clone();
+        let tx_register_asset = move |req: &mut Request| -> IronResult<Response> {
+            self_.process_transaction::<TxRegisterAsset>(req)
+        };
+
+        let self_ = self.clone();
+        let tx_transfer_asset = move |req: &mut Request| -> IronResult<Response> {
+            self_.process_transaction::<TxTransferAsset>(req)
+        };
+
+        // Returns the signable byte range of an unsigned transaction, for an
+        // air-gapped signer that never receives this node's private keys.
+        // The request body is the same JSON shape `process_transaction`
+        // accepts, with a placeholder `signature` (e.g. 128 zero hex digits).
+        let self_ = self.clone();
+        let tx_build = move |req: &mut Request| -> IronResult<Response> {
+            let body = match req.get::<bodyparser::Json>() {
+                Ok(Some(body)) => body,
+                Ok(None) => Err(ApiError::IncorrectRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::IncorrectRequest(Box::new(e)))?,
+            };
+            match self_.build_transaction(&body) {
+                Some(payload) => self_.ok_response(&serde_json::to_value(&payload).unwrap()),
+                None => Err(ApiError::IncorrectRequest(
+                    "Request body does not match any known transaction type".into(),
+                ))?,
+            }
+        };
+
+        // Assembles, verifies and enqueues a transaction from bytes returned
+        // by `/v1/transactions/build` plus a detached signature over them.
+        let self_ = self.clone();
+        let tx_submit = move |req: &mut Request| -> IronResult<Response> {
+            match req.get::<bodyparser::Struct<SignedSubmission>>() {
+                Ok(Some(submission)) => self_.submit_transaction(submission),
+                Ok(None) => Err(ApiError::IncorrectRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::IncorrectRequest(Box::new(e)))?,
+            }
+        };
+
         // Gets status of the wallet corresponding to the public key.
         let self_ = self.clone();
         let wallet_info = move |req: &mut Request| -> IronResult<Response> {
@@ -284,16 +1113,257 @@ impl Api for CryptocurrencyApi {
             let public_key = PublicKey::from_hex(wallet_key).map_err(ApiError::FromHex)?;
 
             let wallet_view = self_.get_wallet_view(public_key);
-            let block =
-                BlockWithState::new(self_.blockchain.snapshot(), SERVICE_ID, 0, wallet_view);
+            let snapshot = self_.blockchain.snapshot();
+            let (nonce, asset_balances) = {
+                let schema = CurrencySchema::ro(&snapshot);
+                let nonce = schema.nonce(&public_key);
+                let asset_balances: Vec<AssetBalanceView> = schema
+                    .assets()
+                    .iter()
+                    .map(|(_, meta)| {
+                        let asset_id = meta.asset_id();
+                        let balance = MapView::new(
+                            &schema.asset_balances(),
+                            asset_balance_key(&public_key, asset_id),
+                        );
+                        AssetBalanceView { asset_id, balance }
+                    })
+                    .collect();
+                (nonce, asset_balances)
+            };
+            let height = latest_height(&snapshot);
+            let block = BlockWithState::new(snapshot, SERVICE_ID, 0, wallet_view, height);
+
+            let mut value = serde_json::to_value(block).unwrap();
+            if let serde_json::Value::Object(ref mut map) = value {
+                map.insert("nonce".into(), serde_json::to_value(nonce).unwrap());
+                map.insert(
+                    "assets".into(),
+                    serde_json::to_value(asset_balances).unwrap(),
+                );
+            }
+            self_.ok_response(&value)
+        };
+
+        // Gets status of an HTLC swap corresponding to its swap id.
+        let self_ = self.clone();
+        let swap_info = move |req: &mut Request| -> IronResult<Response> {
+            let swap_id = req.extensions
+                .get::<Router>()
+                .expect("router::Params not in request extensions")
+                .find("swap_id")
+                .ok_or(ApiError::IncorrectRequest("Missing swap id".into()))?;
+            let swap_id = Hash::from_hex(swap_id).map_err(ApiError::FromHex)?;
+
+            let snapshot = self_.blockchain.snapshot();
+            let height = latest_height(&snapshot);
+            let swap_view = self_.get_swap_view(swap_id);
+            let block = BlockWithState::new(snapshot, SERVICE_ID, 3, swap_view, height);
+
+            self_.ok_response(&serde_json::to_value(block).unwrap())
+        };
+
+        // Gets the preimage revealed by a completed `TxClaim`, if any.
+        let self_ = self.clone();
+        let swap_preimage = move |req: &mut Request| -> IronResult<Response> {
+            let swap_id = req.extensions
+                .get::<Router>()
+                .expect("router::Params not in request extensions")
+                .find("swap_id")
+                .ok_or(ApiError::IncorrectRequest("Missing swap id".into()))?;
+            let swap_id = Hash::from_hex(swap_id).map_err(ApiError::FromHex)?;
+
+            let snapshot = self_.blockchain.snapshot();
+            let height = latest_height(&snapshot);
+            let preimage_view = self_.get_preimage_view(swap_id);
+            let block = BlockWithState::new(snapshot, SERVICE_ID, 4, preimage_view, height);
 
             self_.ok_response(&serde_json::to_value(block).unwrap())
         };
 
+        // Gets metadata of a registered asset corresponding to its id.
+        let self_ = self.clone();
+        let asset_info = move |req: &mut Request| -> IronResult<Response> {
+            let asset_id = req.extensions
+                .get::<Router>()
+                .expect("router::Params not in request extensions")
+                .find("asset_id")
+                .ok_or(ApiError::IncorrectRequest("Missing asset id".into()))?;
+            let asset_id: AssetId = asset_id
+                .parse()
+                .map_err(|_| ApiError::IncorrectRequest("Invalid asset id".into()))?;
+
+            let snapshot = self_.blockchain.snapshot();
+            let height = latest_height(&snapshot);
+            let asset_view = self_.get_asset_view(asset_id);
+            let block = BlockWithState::new(snapshot, SERVICE_ID, 5, asset_view, height);
+
+            self_.ok_response(&serde_json::to_value(block).unwrap())
+        };
+
+        // Gets the balance a wallet held at or before a given height, proven
+        // by position against the wallet's `history_hash` as committed in
+        // its current `Wallet` record.
+        let self_ = self.clone();
+        let wallet_info_at_height = move |req: &mut Request| -> IronResult<Response> {
+            let (wallet_key, target_height) = {
+                let params = req.extensions
+                    .get::<Router>()
+                    .expect("router::Params not in request extensions");
+                let wallet_key = params
+                    .find("pub_key")
+                    .ok_or(ApiError::IncorrectRequest("Missing public key".into()))?;
+                let target_height = params
+                    .find("height")
+                    .ok_or(ApiError::IncorrectRequest("Missing height".into()))?;
+                let target_height: u64 = target_height
+                    .parse()
+                    .map_err(|_| ApiError::IncorrectRequest("Invalid height".into()))?;
+                (PublicKey::from_hex(wallet_key).map_err(ApiError::FromHex)?, target_height)
+            };
+
+            let snapshot = self_.blockchain.snapshot();
+            let tip = latest_height(&snapshot);
+            if target_height > tip.0 {
+                Err(ApiError::IncorrectRequest(
+                    "Requested height exceeds the current chain tip".into(),
+                ))?;
+            }
+
+            let combined = {
+                let schema = CurrencySchema::ro(&snapshot);
+                let wallet_view = MapView::new(&schema.wallets(), wallet_key);
+
+                let history = schema.balance_history(&wallet_key);
+                let found = (0..history.len())
+                    .rev()
+                    .find(|&i| history.get(i).map_or(false, |s| s.height() <= target_height));
+                let history_view = match found {
+                    Some(index) => ListView::new(&history, index),
+                    None => Err(ApiError::IncorrectRequest(
+                        "No balance recorded for this wallet at or before the requested height"
+                            .into(),
+                    ))?,
+                };
+
+                WalletHistoryProof {
+                    wallet: wallet_view,
+                    history: history_view,
+                }
+            };
+            // The block/precommit proof below anchors to the current chain
+            // tip, not `target_height` (a `ProofMapIndex` keeps no historical
+            // roots to prove against). The historical guarantee for the
+            // returned balance instead comes from chaining `combined.wallet`'s
+            // `MapProof` (which ties the wallet's current `history_hash` to
+            // `state_hash`) to `combined.history`'s `ListProof` (whose root a
+            // verifier must check equals that same `history_hash`).
+            let block = BlockWithState::new(snapshot, SERVICE_ID, 0, combined, tip);
+
+            self_.ok_response(&serde_json::to_value(block).unwrap())
+        };
+
+        // A UTXO-style point query scoped to *this service* (`SERVICE_ID`):
+        // given one of `CurrencySchema`'s table ids (matching the order of
+        // `CurrencySchema::state_hash`) and a key, returns the stored value
+        // (if any) with its `MapProof`. This is not a generic cross-service
+        // primitive — `service_id` is hardcoded and each `table_id` is
+        // decoded with this service's own key type (`PublicKey` for 0-2,
+        // a digest `Hash` for 3-6, per `derive_swap_id` for 3-4 and
+        // `asset_key`/`asset_balance_key` for 5-6), so a
+        // caller still needs to know this service's schema, just not which
+        // REST route maps to which table. Tables keyed by a digest
+        // (`assets`, `asset_balances`) require the caller to already know
+        // that digest, e.g. from `/v1/asset/:asset_id`.
+        let self_ = self.clone();
+        let cryptocurrency_state_query = move |req: &mut Request| -> IronResult<Response> {
+            let (table_id, key_hex) = {
+                let params = req.extensions
+                    .get::<Router>()
+                    .expect("router::Params not in request extensions");
+                let table_id = params
+                    .find("table_id")
+                    .ok_or(ApiError::IncorrectRequest("Missing table id".into()))?;
+                let table_id: usize = table_id
+                    .parse()
+                    .map_err(|_| ApiError::IncorrectRequest("Invalid table id".into()))?;
+                let key_hex = params
+                    .find("key")
+                    .ok_or(ApiError::IncorrectRequest("Missing key".into()))?
+                    .to_owned();
+                (table_id, key_hex)
+            };
+
+            let snapshot = self_.blockchain.snapshot();
+            let height = latest_height(&snapshot);
+
+            let value = match table_id {
+                0 => {
+                    let key = PublicKey::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).wallets(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 0, view, height))
+                }
+                1 => {
+                    let key = PublicKey::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).faucet_records(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 1, view, height))
+                }
+                2 => {
+                    let key = PublicKey::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).nonces(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 2, view, height))
+                }
+                3 => {
+                    let key = Hash::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).swaps(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 3, view, height))
+                }
+                4 => {
+                    let key = Hash::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).revealed_preimages(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 4, view, height))
+                }
+                5 => {
+                    let key = Hash::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).assets(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 5, view, height))
+                }
+                6 => {
+                    let key = Hash::from_hex(&key_hex).map_err(ApiError::FromHex)?;
+                    let view = MapView::new(&CurrencySchema::ro(&snapshot).asset_balances(), key);
+                    serde_json::to_value(BlockWithState::new(snapshot, SERVICE_ID, 6, view, height))
+                }
+                _ => Err(ApiError::IncorrectRequest("Unknown table id".into()))?,
+            };
+
+            self_.ok_response(&value.unwrap())
+        };
+
         // Bind the transaction handler to a specific route.
         router.post("/v1/wallets", tx_create, "tx_create");
         router.post("/v1/wallets/transfer", tx_transfer, "tx_transfer");
+        router.post("/v1/faucet", tx_faucet, "tx_faucet");
+        router.post("/v1/swaps/lock", tx_lock, "tx_lock");
+        router.post("/v1/swaps/claim", tx_claim, "tx_claim");
+        router.post("/v1/swaps/refund", tx_refund, "tx_refund");
+        router.post("/v1/assets", tx_register_asset, "tx_register_asset");
+        router.post("/v1/assets/transfer", tx_transfer_asset, "tx_transfer_asset");
+        router.post("/v1/transactions/build", tx_build, "tx_build");
+        router.post("/v1/transactions/submit", tx_submit, "tx_submit");
         router.get("/v1/wallet/:pub_key", wallet_info, "wallet_info");
+        router.get(
+            "/v1/wallet/:pub_key/at/:height",
+            wallet_info_at_height,
+            "wallet_info_at_height",
+        );
+        router.get("/v1/swap/:swap_id", swap_info, "swap_info");
+        router.get("/v1/swap/:swap_id/preimage", swap_preimage, "swap_preimage");
+        router.get("/v1/asset/:asset_id", asset_info, "asset_info");
+        router.get(
+            "/v1/state/:table_id/:key",
+            cryptocurrency_state_query,
+            "cryptocurrency_state_query",
+        );
     }
 }
 
@@ -319,16 +1389,7 @@ impl Service for CurrencyService {
 
     /// Implement a method to deserialize transactions coming to the node.
     fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<Transaction>, encoding::Error> {
-        let trans: Box<Transaction> = match raw.message_type() {
-            TX_TRANSFER_ID => Box::new(TxTransfer::from_raw(raw)?),
-            TX_CREATE_WALLET_ID => Box::new(TxCreateWallet::from_raw(raw)?),
-            _ => {
-                return Err(encoding::Error::IncorrectMessageType {
-                    message_type: raw.message_type(),
-                });
-            }
-        };
-        Ok(trans)
+        transaction_from_raw(raw)
     }
 
     /// Create a REST `Handler` to process web requests to the node.
@@ -392,3 +1453,122 @@ fn main() {
     println!("Blockchain is ready for transactions!");
     node.run().unwrap();
 }
+
+// // // // // // // // // // TESTS // // // // // // // // // //
+
+/// Execute()-level smoke tests for the balance-affecting invariants that have
+/// regressed once already during review (faucet overflow, dead-on-arrival
+/// asset minting): each test runs a transaction straight against a fresh
+/// `Fork`, without going through the network or consensus layers.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exonum::storage::Database;
+
+    fn wallet_of(fork: &Fork, pub_key: &PublicKey) -> Option<Wallet> {
+        CurrencySchema::ro(fork).wallet(pub_key)
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let bytes = vec![0u8, 1, 255, 16, 192];
+        assert_eq!(from_hex(&to_hex(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn from_hex_rejects_non_ascii_instead_of_panicking() {
+        assert!(from_hex("a\u{20ac}").is_err());
+    }
+
+    #[test]
+    fn faucet_withdrawal_rejects_amount_overflow() {
+        let db = MemoryDB::new();
+        let mut fork = db.fork();
+        let (pub_key, sec_key) = crypto::gen_keypair();
+
+        TxCreateWallet::new(&pub_key, "Alice", &sec_key).execute(&mut fork);
+        let before = wallet_of(&fork, &pub_key).unwrap().balance();
+
+        TxFaucetWithdraw::new(&pub_key, u64::max_value(), &sec_key).execute(&mut fork);
+
+        let after = wallet_of(&fork, &pub_key).unwrap().balance();
+        assert_eq!(before, after, "an overflowing withdrawal must not mutate the wallet");
+    }
+
+    #[test]
+    fn transfer_rejects_wrong_nonce() {
+        let db = MemoryDB::new();
+        let mut fork = db.fork();
+        let (alice, alice_sec) = crypto::gen_keypair();
+        let (bob, bob_sec) = crypto::gen_keypair();
+
+        TxCreateWallet::new(&alice, "Alice", &alice_sec).execute(&mut fork);
+        TxCreateWallet::new(&bob, "Bob", &bob_sec).execute(&mut fork);
+
+        TxTransfer::new(&alice, &bob, 10, 1, &alice_sec).execute(&mut fork);
+
+        assert_eq!(wallet_of(&fork, &alice).unwrap().balance(), INIT_BALANCE);
+        assert_eq!(wallet_of(&fork, &bob).unwrap().balance(), INIT_BALANCE);
+    }
+
+    #[test]
+    fn register_asset_credits_registrant_with_initial_supply() {
+        let db = MemoryDB::new();
+        let mut fork = db.fork();
+        let (pub_key, sec_key) = crypto::gen_keypair();
+
+        TxRegisterAsset::new(&pub_key, 7, 100, 500, "Gold", &sec_key).execute(&mut fork);
+
+        assert_eq!(CurrencySchema::ro(&fork).asset_balance(&pub_key, 7), 500);
+    }
+
+    #[test]
+    fn htlc_claim_credits_receiver_and_removes_swap() {
+        let db = MemoryDB::new();
+        let mut fork = db.fork();
+        let (alice, alice_sec) = crypto::gen_keypair();
+        let (bob, bob_sec) = crypto::gen_keypair();
+
+        TxCreateWallet::new(&alice, "Alice", &alice_sec).execute(&mut fork);
+        TxCreateWallet::new(&bob, "Bob", &bob_sec).execute(&mut fork);
+
+        let preimage = b"top secret";
+        let hashlock = crypto::hash(preimage);
+        let timeout = 1_000;
+        TxLock::new(&alice, &bob, 10, &hashlock, timeout, &alice_sec).execute(&mut fork);
+
+        let swap_id = derive_swap_id(&alice, &bob, &hashlock, timeout);
+        assert!(CurrencySchema::ro(&fork).swap(&swap_id).is_some());
+        assert_eq!(wallet_of(&fork, &alice).unwrap().balance(), INIT_BALANCE - 10);
+
+        TxClaim::new(&bob, &swap_id, preimage, &bob_sec).execute(&mut fork);
+
+        assert_eq!(wallet_of(&fork, &bob).unwrap().balance(), INIT_BALANCE + 10);
+        assert!(CurrencySchema::ro(&fork).swap(&swap_id).is_none());
+    }
+
+    #[test]
+    fn lock_cannot_be_squatted_by_a_different_sender() {
+        let db = MemoryDB::new();
+        let mut fork = db.fork();
+        let (alice, alice_sec) = crypto::gen_keypair();
+        let (bob, bob_sec) = crypto::gen_keypair();
+        let (mallory, mallory_sec) = crypto::gen_keypair();
+
+        TxCreateWallet::new(&alice, "Alice", &alice_sec).execute(&mut fork);
+        TxCreateWallet::new(&bob, "Bob", &bob_sec).execute(&mut fork);
+
+        let hashlock = crypto::hash(b"shared secret");
+        let timeout = 1_000;
+
+        // Mallory's lock quotes Alice's swap terms but is signed by Mallory,
+        // so it derives to a different swap id (it includes Mallory's own
+        // `from`) and cannot collide with Alice's.
+        TxLock::new(&mallory, &bob, 10, &hashlock, timeout, &mallory_sec).execute(&mut fork);
+        TxLock::new(&alice, &bob, 10, &hashlock, timeout, &alice_sec).execute(&mut fork);
+
+        let alice_swap_id = derive_swap_id(&alice, &bob, &hashlock, timeout);
+        let swap = CurrencySchema::ro(&fork).swap(&alice_swap_id).unwrap();
+        assert_eq!(*swap.from(), alice);
+    }
+}